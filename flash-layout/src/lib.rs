@@ -92,15 +92,56 @@ impl<'a> FlashLayout<'a> {
         None
     }
 
-    /*
+    /// Turn an arbitrary byte range into the set of erase blocks overlapping it
+    ///
+    /// `addr_start` must land inside some erase block (see [`FlashLayout::find_eb_by_addr`]);
+    /// `addr_end` need not be block-aligned -- the returned [`Range`] yields every erase block
+    /// that starts before `addr_end`, including a final partially-covered block.
     pub fn eb_range_from_addr_range(&self, addr_start: u64, addr_end: u64) -> Option<Range<'a>> {
-        todo!()
+        let (eb, _offs_in_eb) = self.find_eb_by_addr(addr_start)?;
+        Some(Range {
+            layout: *self,
+            addr_start,
+            addr_end,
+            cursor: eb,
+        })
+    }
+
+    /// Split this layout into one handle per [`Region`]
+    ///
+    /// Each region has its own uniform erase size, so a [`RegionHandle`] can be driven on its
+    /// own wherever a caller only wants to deal with one erase size at a time.
+    pub fn into_regions(&self) -> impl Iterator<Item = RegionHandle<'a>> {
+        self.regions.into_iter().map(|region| RegionHandle { region })
+    }
+
+    /// Check whether any region overlapping `[addr_start, addr_start + len)` is write-protected
+    ///
+    /// Returns the first write-protected region found, or `Ok(())` if none of the overlapping
+    /// regions are protected.
+    pub fn check_for_unwritable_regions(&self, addr_start: u64, len: u64) -> Result<(), Region> {
+        let addr_end = addr_start + len;
+
+        for region in self.regions {
+            if region.addr_start() >= addr_end {
+                break;
+            }
+
+            if region.addr_end() <= addr_start {
+                continue;
+            }
+
+            if region.write_prot {
+                return Err(*region);
+            }
+        }
+
+        Ok(())
     }
-    */
 }
 
 /// A region within a flash device which contains a particular size and number of erase blocks
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct Region {
     /// base address of this region (address of the first erase block)
     pub addr: u64,
@@ -110,6 +151,12 @@ pub struct Region {
 
     /// Number of erase blocks within this region
     pub eb_count: u32,
+
+    /// Whether this region rejects reads (e.g. option bytes, a bootloader's protected area)
+    pub read_prot: bool,
+
+    /// Whether this region rejects erases/programs
+    pub write_prot: bool,
 }
 
 impl Region {
@@ -130,6 +177,60 @@ impl Region {
     }
 }
 
+/// A single [`Region`] handed out by [`FlashLayout::into_regions`]
+///
+/// Reports a single, uniform erase size and bounds-checks addresses to
+/// `[Self::addr_start(), Self::addr_end())`, so it can stand in for a whole `FlashLayout` (see
+/// [`RegionHandle::layout`]) when bridging to APIs that only understand one erase size at a time.
+#[derive(Debug, Clone, Copy)]
+pub struct RegionHandle<'a> {
+    region: &'a Region,
+}
+
+impl<'a> RegionHandle<'a> {
+    /// Number of bytes per erase block
+    pub fn eb_bytes(&self) -> u32 {
+        self.region.eb_bytes
+    }
+
+    /// Number of erase blocks in this region
+    pub fn eb_count(&self) -> u32 {
+        self.region.eb_count
+    }
+
+    pub fn addr_start(&self) -> u64 {
+        self.region.addr_start()
+    }
+
+    pub fn addr_end(&self) -> u64 {
+        self.region.addr_end()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.region.len()
+    }
+
+    /// A [`FlashLayout`] covering only this region
+    pub fn layout(&self) -> FlashLayout<'a> {
+        FlashLayout::new(core::slice::from_ref(self.region))
+    }
+
+    /// Locate the erase block containing `addr`, and the offset of `addr` within it
+    ///
+    /// Returns `None` if `addr` falls outside `[Self::addr_start(), Self::addr_end())`.
+    pub fn find_eb_by_addr(&self, addr: u64) -> Option<(u32, u32)> {
+        if !self.region.contains_addr(addr) {
+            return None;
+        }
+
+        let addr_in_r = addr - self.region.addr_start();
+        Some((
+            (addr_in_r / self.region.eb_bytes as u64).try_into().unwrap(),
+            (addr_in_r % self.region.eb_bytes as u64).try_into().unwrap(),
+        ))
+    }
+}
+
 /// A single erase block within a Region
 #[derive(Debug, Clone)]
 pub struct EraseBlock<'a> {
@@ -170,37 +271,31 @@ impl<'a> EraseBlock<'a> {
         self.region().eb_bytes
     }
 
-    /*
-    /// Erase block number (within the containing `Layout`) of this erase block
+    /// Erase block number (within the containing [`FlashLayout`]) of this erase block
+    ///
+    /// The exact inverse of [`FlashLayout::find_eb_by_eb_num`].
     pub fn eb_num(&self) -> u32 {
-        todo!()
+        let preceding: u32 = self.layout.regions[..self.region_idx]
+            .iter()
+            .map(|r| r.eb_count)
+            .sum();
+        preceding + self.eb_offs_in_region
     }
-    */
 }
 
-/// A flat sequence of erase blocks
+/// A flat sequence of erase blocks, as produced by [`FlashLayout::eb_range_from_addr_range`]
 #[derive(Debug, Clone)]
 pub struct Range<'a> {
     layout: FlashLayout<'a>,
-    first_eb: EraseBlock<'a>,
+    addr_start: u64,
     addr_end: u64,
+    /// Next erase block to yield
+    cursor: EraseBlock<'a>,
 }
 
 impl<'a> Range<'a> {
     pub fn addr_start(&self) -> u64 {
-        let byte_ct = {
-            let mut byte_ct = 0u64;
-            for (i, r) in self.layout.regions.into_iter().enumerate() {
-                if i == self.first_eb.region_idx {
-                    break;
-                }
-                byte_ct += r.len();
-            }
-
-            byte_ct
-                + self.first_eb.eb_offs_in_region as u64 * self.first_eb.region().eb_bytes as u64
-        };
-        self.layout.addr_start() + byte_ct
+        self.addr_start
     }
 
     pub fn addr_end(&self) -> u64 {
@@ -224,23 +319,21 @@ impl<'a> Iterator for Range<'a> {
     type Item = EraseBlock<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.first_eb.region_idx > self.layout.regions.len() {
+        if self.cursor.region_idx >= self.layout.regions.len() {
             return None;
         }
-        if self.first_eb.addr_end() >= self.addr_end {
+        if self.cursor.addr_start() >= self.addr_end {
             return None;
         }
 
-        if self.first_eb.eb_offs_in_region >= self.first_eb.region().eb_count {
-            let next_region = self.first_eb.region_idx + 1;
-            if next_region > self.layout.regions.len() {
-                // TODO: consider short circuiting here?
-                return None;
-            }
-        }
+        let current = self.cursor.clone();
 
-        self.first_eb.eb_offs_in_region += 1;
+        self.cursor.eb_offs_in_region += 1;
+        if self.cursor.eb_offs_in_region >= self.cursor.region().eb_count {
+            self.cursor.eb_offs_in_region = 0;
+            self.cursor.region_idx += 1;
+        }
 
-        Some(self.first_eb.clone())
+        Some(current)
     }
 }
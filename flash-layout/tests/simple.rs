@@ -6,8 +6,135 @@ fn ok_layout() {
         addr: 100,
         eb_bytes: 4,
         eb_count: 4,
+        read_prot: false,
+        write_prot: false,
     }]);
 
     assert_eq!(layout.addr_start(), 100);
     assert_eq!(layout.addr_end(), 100 + 4 * 4);
 }
+
+#[test]
+fn into_regions_per_region_bounds() {
+    let layout = FlashLayout::new(&[
+        Region {
+            addr: 0,
+            eb_bytes: 16,
+            eb_count: 4,
+            read_prot: false,
+            write_prot: false,
+        },
+        Region {
+            addr: 64,
+            eb_bytes: 64,
+            eb_count: 2,
+            read_prot: false,
+            write_prot: false,
+        },
+    ]);
+
+    let regions: Vec<_> = layout.into_regions().collect();
+    assert_eq!(regions.len(), 2);
+
+    assert_eq!(regions[0].eb_bytes(), 16);
+    assert_eq!(regions[0].addr_start(), 0);
+    assert_eq!(regions[0].addr_end(), 64);
+    assert_eq!(regions[0].find_eb_by_addr(20), Some((1, 4)));
+    assert_eq!(regions[0].find_eb_by_addr(64), None);
+
+    assert_eq!(regions[1].eb_bytes(), 64);
+    assert_eq!(regions[1].addr_start(), 64);
+    assert_eq!(regions[1].addr_end(), 192);
+    assert_eq!(regions[1].find_eb_by_addr(64), Some((0, 0)));
+    assert_eq!(regions[1].find_eb_by_addr(0), None);
+}
+
+#[test]
+fn unwritable_region_is_reported() {
+    let protected = Region {
+        addr: 16,
+        eb_bytes: 16,
+        eb_count: 1,
+        read_prot: false,
+        write_prot: true,
+    };
+    let regions = [
+        Region {
+            addr: 0,
+            eb_bytes: 16,
+            eb_count: 1,
+            read_prot: false,
+            write_prot: false,
+        },
+        protected,
+        Region {
+            addr: 32,
+            eb_bytes: 16,
+            eb_count: 1,
+            read_prot: false,
+            write_prot: false,
+        },
+    ];
+    let layout = FlashLayout::new(&regions);
+
+    assert_eq!(layout.check_for_unwritable_regions(0, 16), Ok(()));
+    assert_eq!(layout.check_for_unwritable_regions(8, 16), Err(protected));
+    assert_eq!(layout.check_for_unwritable_regions(32, 16), Ok(()));
+}
+
+fn multi_region_layout() -> Vec<Region> {
+    vec![
+        Region {
+            addr: 0,
+            eb_bytes: 16,
+            eb_count: 4,
+            read_prot: false,
+            write_prot: false,
+        },
+        Region {
+            addr: 64,
+            eb_bytes: 32,
+            eb_count: 2,
+            read_prot: false,
+            write_prot: false,
+        },
+    ]
+}
+
+#[test]
+fn eb_range_spans_a_region_boundary() {
+    let regions = multi_region_layout();
+    let layout = FlashLayout::new(&regions);
+
+    // starts in the last block of region 0 (48..64), ends exactly where the second block of
+    // region 1 (96..128) begins -- that second block should not be yielded.
+    let range = layout.eb_range_from_addr_range(48, 96).unwrap();
+    assert_eq!(range.addr_start(), 48);
+    assert_eq!(range.addr_end(), 96);
+
+    let ebs: Vec<_> = range.map(|eb| eb.addr_start()).collect();
+    assert_eq!(ebs, vec![48, 64]);
+}
+
+#[test]
+fn eb_range_starting_mid_region() {
+    let regions = multi_region_layout();
+    let layout = FlashLayout::new(&regions);
+
+    // addr 20 is mid-way through the block covering 16..32.
+    let range = layout.eb_range_from_addr_range(20, 40).unwrap();
+    let ebs: Vec<_> = range.map(|eb| eb.addr_start()).collect();
+    assert_eq!(ebs, vec![16, 32]);
+}
+
+#[test]
+fn eb_num_is_the_inverse_of_find_eb_by_eb_num() {
+    let regions = multi_region_layout();
+    let layout = FlashLayout::new(&regions);
+
+    for eb_num in 0..6 {
+        let eb = layout.find_eb_by_eb_num(eb_num).unwrap();
+        assert_eq!(eb.eb_num(), eb_num);
+    }
+    assert!(layout.find_eb_by_eb_num(6).is_none());
+}
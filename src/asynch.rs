@@ -0,0 +1,146 @@
+//! An async counterpart to [`Flash`], for devices whose erases take tens to hundreds of
+//! milliseconds and shouldn't block the executor for the duration
+//!
+//! [`Flash`] is peppered with `XXX: ASYNC!` for exactly this reason. [`AsyncFlash`] mirrors it
+//! with `async fn`s for the operations that actually take device time, and [`YieldingFlash`]
+//! adapts any blocking [`Flash`] to it by yielding to the executor around each erase/write.
+
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::Poll;
+
+use flash_layout::FlashLayout;
+
+use crate::{Flash, FlashOp, ProgramError};
+
+/// Async counterpart to [`Flash`]
+pub trait AsyncFlash {
+    /// Does this erase to 0 or 1?
+    fn erases_to_zero(&self) -> bool;
+
+    /// Minimum size (and alignment) of a single `program` call, in bytes
+    fn write_size(&self) -> usize;
+
+    /// Whether a second `program` to the same erase block is permitted before the next erase
+    fn supports_multiwrite(&self) -> bool;
+
+    /// Read a span of bytes out of a sector
+    ///
+    /// Reads are assumed to be fast enough not to need a yield point.
+    fn read(&self, sector: usize, addr: usize, buf: &mut [u8]);
+
+    async fn run_op(&mut self, op: Pin<FlashOp<'_>>);
+
+    /// erase a given sector
+    async fn erase_sector(&mut self, sector: usize) -> Result<(), ()>;
+
+    /// program some piece of a sector
+    async fn program(&mut self, sector: usize, addr: usize, data: &[u8]) -> Result<(), ProgramError>;
+}
+
+/// Cooperatively yield once to the executor
+///
+/// Resolves `Pending` the first time it's polled, immediately re-waking itself so the executor
+/// reschedules it, then `Ready` on the next poll. This doesn't depend on any particular
+/// executor, unlike e.g. `tokio::task::yield_now`.
+pub async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(move |cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// Adapts a blocking [`Flash`] to [`AsyncFlash`] by yielding to the executor between each erase
+/// block and between write chunks
+pub struct YieldingFlash<F: Flash> {
+    flash: F,
+    /// Max bytes written per `program` call before yielding; defaults to `flash.write_size()`
+    write_chunk_bytes: usize,
+}
+
+impl<F: Flash> YieldingFlash<F> {
+    pub fn new(flash: F) -> Self {
+        let write_chunk_bytes = flash.write_size();
+        Self {
+            flash,
+            write_chunk_bytes,
+        }
+    }
+
+    /// Override how many bytes are written (via the underlying [`Flash::program`]) before
+    /// yielding to the executor; must be a multiple of `flash.write_size()`
+    pub fn with_write_chunk_bytes(mut self, write_chunk_bytes: usize) -> Self {
+        self.write_chunk_bytes = write_chunk_bytes;
+        self
+    }
+}
+
+impl<F: Flash> AsyncFlash for YieldingFlash<F> {
+    fn erases_to_zero(&self) -> bool {
+        self.flash.erases_to_zero()
+    }
+
+    fn write_size(&self) -> usize {
+        self.flash.write_size()
+    }
+
+    fn supports_multiwrite(&self) -> bool {
+        self.flash.supports_multiwrite()
+    }
+
+    fn read(&self, sector: usize, addr: usize, buf: &mut [u8]) {
+        self.flash.read(sector, addr, buf)
+    }
+
+    async fn run_op(&mut self, op: Pin<FlashOp<'_>>) {
+        self.flash.run_op(op);
+        yield_now().await;
+    }
+
+    async fn erase_sector(&mut self, sector: usize) -> Result<(), ()> {
+        let result = self.flash.erase_sector(sector);
+        yield_now().await;
+        result
+    }
+
+    async fn program(&mut self, sector: usize, addr: usize, data: &[u8]) -> Result<(), ProgramError> {
+        // `write_chunk_bytes` mirrors `flash.write_size()`, which devices with no minimum write
+        // size report as 0 (see `MockFlash::program`) -- `chunks` panics on a 0 size, so treat
+        // that as "no chunking", i.e. one chunk covering the whole write.
+        let chunk_bytes = if self.write_chunk_bytes == 0 {
+            data.len().max(1)
+        } else {
+            self.write_chunk_bytes
+        };
+        for (i, chunk) in data.chunks(chunk_bytes).enumerate() {
+            self.flash.program(sector, addr + i * chunk_bytes, chunk)?;
+            yield_now().await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Async counterpart to [`Mtxn`](crate::Mtxn), built on [`AsyncFlash`]
+///
+/// Holds the device and its layout; it has no commit path, and issues no erase or program ops.
+pub struct AsyncMtxn<'a, F: AsyncFlash> {
+    flash: F,
+    layout: FlashLayout<'a>,
+}
+
+impl<'a, F: AsyncFlash> AsyncMtxn<'a, F> {
+    pub fn new(flash: F, layout: FlashLayout<'a>) -> Self {
+        Self { flash, layout }
+    }
+
+    // TODO: wire `Mtxn::check_writable` in (or call it on `self.layout` directly) once this
+    // actually issues ops.
+}
@@ -0,0 +1,124 @@
+//! Present several physical flash devices as one contiguous logical address space
+//!
+//! Dual-bank parts, or designs that pair an internal flash with an external SPI chip, want one
+//! logical address space rather than juggling which physical device backs which address.
+//! [`ConcatFlash`] concatenates two [`Flash`] devices (and their [`FlashLayout`]s) end to end;
+//! nest `ConcatFlash<ConcatFlash<A, B>, C>` to chain more than two.
+
+use core::pin::Pin;
+
+use flash_layout::{FlashLayout, Region};
+
+use crate::{Flash, FlashOp, ProgramError};
+
+/// Concatenates two [`Flash`] devices into one logical address space
+///
+/// `b`'s [`Region`]s are offset so they begin where `a`'s [`FlashLayout`] ends.
+/// [`ConcatFlash::layout`] returns the combined [`FlashLayout`], so `find_eb_by_addr`/
+/// `find_eb_by_eb_num` work across the seam between the two devices same as for a single one.
+pub struct ConcatFlash<A: Flash, B: Flash> {
+    a: A,
+    b: B,
+    /// First address belonging to `b`; everything below this routes to `a`
+    boundary: u64,
+    /// `a`'s regions followed by `b`'s, with `b`'s `addr` fields offset by `boundary`
+    regions: Vec<Region>,
+}
+
+impl<A: Flash, B: Flash> ConcatFlash<A, B> {
+    pub fn new(a: A, a_layout: FlashLayout<'_>, b: B, b_layout: FlashLayout<'_>) -> Self {
+        assert_eq!(
+            a.erases_to_zero(),
+            b.erases_to_zero(),
+            "ConcatFlash requires both devices to agree on erase polarity"
+        );
+        assert_eq!(
+            a.write_size(),
+            b.write_size(),
+            "ConcatFlash requires both devices to agree on write size"
+        );
+
+        let boundary = a_layout.addr_end();
+        let mut regions = Vec::with_capacity(a_layout.regions.len() + b_layout.regions.len());
+        regions.extend_from_slice(a_layout.regions);
+        regions.extend(b_layout.regions.iter().map(|r| Region {
+            addr: r.addr + boundary,
+            ..*r
+        }));
+
+        Self {
+            a,
+            b,
+            boundary,
+            regions,
+        }
+    }
+
+    /// The combined address space of both devices
+    pub fn layout(&self) -> FlashLayout<'_> {
+        FlashLayout::new(&self.regions)
+    }
+
+    /// Route a global sector address to the child device that owns it, and that child's own
+    /// (un-offset) sector address
+    ///
+    /// Returns `None` if `sector` doesn't land on an erase block in either device -- e.g. it
+    /// falls in the gap a misaligned `boundary` would leave, which would otherwise show up as an
+    /// op silently straddling the seam between the two devices.
+    fn route(&self, sector: usize) -> Option<Child> {
+        self.layout().find_eb_by_addr(sector as u64)?;
+
+        Some(if (sector as u64) < self.boundary {
+            Child::A(sector)
+        } else {
+            Child::B(sector - self.boundary as usize)
+        })
+    }
+}
+
+enum Child {
+    A(usize),
+    B(usize),
+}
+
+impl<A: Flash, B: Flash> Flash for ConcatFlash<A, B> {
+    fn erases_to_zero(&self) -> bool {
+        self.a.erases_to_zero()
+    }
+
+    fn write_size(&self) -> usize {
+        self.a.write_size()
+    }
+
+    fn supports_multiwrite(&self) -> bool {
+        self.a.supports_multiwrite() && self.b.supports_multiwrite()
+    }
+
+    fn read(&self, sector: usize, addr: usize, buf: &mut [u8]) {
+        match self.route(sector) {
+            Some(Child::A(s)) => self.a.read(s, addr, buf),
+            Some(Child::B(s)) => self.b.read(s, addr, buf),
+            None => panic!("ConcatFlash: sector {sector:#x} is not an erase block of either device"),
+        }
+    }
+
+    fn run_op(&mut self, _op: Pin<FlashOp<'_>>) {
+        unimplemented!("ConcatFlash does not implement op queuing yet")
+    }
+
+    fn erase_sector(&mut self, sector: usize) -> Result<(), ()> {
+        match self.route(sector) {
+            Some(Child::A(s)) => self.a.erase_sector(s),
+            Some(Child::B(s)) => self.b.erase_sector(s),
+            None => Err(()),
+        }
+    }
+
+    fn program(&mut self, sector: usize, addr: usize, data: &[u8]) -> Result<(), ProgramError> {
+        match self.route(sector) {
+            Some(Child::A(s)) => self.a.program(s, addr, data),
+            Some(Child::B(s)) => self.b.program(s, addr, data),
+            None => Err(ProgramError::OutOfRange),
+        }
+    }
+}
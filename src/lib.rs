@@ -136,6 +136,10 @@
 //!   - log
 //!   - value
 
+use core::pin::Pin;
+
+use flash_layout::{FlashLayout, Region};
+
 pub struct SectorSpec {
     /// base address of this sector
     pub addr: usize,
@@ -143,6 +147,7 @@ pub struct SectorSpec {
     pub len:  usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProgramError {
     /// Attempt to move a bit back to it's erased state
     BitUnsetAttempt,
@@ -152,24 +157,26 @@ pub enum ProgramError {
     WriteAfterWrite,
     /// Many flash devices require that writes be aligned (at least to their size)
     WriteUnaligned,
+    /// The requested sector does not exist on this device
+    OutOfRange,
 }
 
-pub enum FlashOpKind {
+pub enum FlashOpKind<'a> {
     Erase { sector: usize },
-    Program { sector: usize, addr: usize, data: &[u8] },
+    Program { sector: usize, addr: usize, data: &'a [u8] },
 }
 
-pub struct FlashOp {
+pub struct FlashOp<'a> {
     // XXX: need intrusive list
 
-    kind: FlashOpKind,
+    kind: FlashOpKind<'a>,
 
     // XXX: in C, we presume that the callback can use `container_of` on the `FlashOp` parameter to
     // obtain a reference to their data. It might be reasonable to provide a field to contain it
     // instead
     //
     // XXX: consider if we can without-cost support a Fn type here instead of a basic function
-    callback: fn(Pin<FlashOp>, &mut Flash, Result<(), ProgramError>),
+    callback: fn(Pin<FlashOp<'a>>, &mut dyn Flash, Result<(), ProgramError>),
 }
 
 /// Abstract flash API
@@ -178,7 +185,22 @@ pub trait Flash {
     /// Does this erase to 0 or 1?
     fn erases_to_zero(&self) -> bool;
 
-    fn run_op(&mut self, op: Pin<FlashOp>);
+    /// Minimum size (and alignment) of a single `program` call, in bytes
+    fn write_size(&self) -> usize;
+
+    /// Whether a second `program` to the same erase block is permitted before the next erase
+    ///
+    /// Devices that only allow one write per erased region (or that enforce a strict
+    /// write-after-write policy incompatible with re-writing) must return `false` here.
+    fn supports_multiwrite(&self) -> bool;
+
+    /// Read a span of bytes out of a sector
+    ///
+    /// Unlike `erase_sector`/`program`, reads are assumed to be synchronous and not go through
+    /// `run_op`.
+    fn read(&self, sector: usize, addr: usize, buf: &mut [u8]);
+
+    fn run_op(&mut self, op: Pin<FlashOp<'_>>);
 
     /// erase a given sector
     //
@@ -192,8 +214,32 @@ pub trait Flash {
 }
 
 /// Mtxn - a transactional kv store
-pub struct Mtxn<F: Flash> {
-    flash: F, 
+pub struct Mtxn<'a, F: Flash> {
+    flash: F,
+    layout: FlashLayout<'a>,
 
     //
 }
+
+impl<'a, F: Flash> Mtxn<'a, F> {
+    pub fn new(flash: F, layout: FlashLayout<'a>) -> Self {
+        Self { flash, layout }
+    }
+
+    /// Check that issuing a `FlashOpKind::Erase`/`FlashOpKind::Program` covering
+    /// `[addr_start, addr_start + len)` won't hit a write-protected region
+    ///
+    /// Must be called before any such op is issued -- on real devices, writing a protected
+    /// region is a silent hardware fault rather than a reported error.
+    //
+    // TODO: wire into the commit path once `Mtxn` actually issues ops -- unused until then.
+    #[allow(dead_code)]
+    fn check_writable(&self, addr_start: u64, len: u64) -> Result<(), Region> {
+        self.layout.check_for_unwritable_regions(addr_start, len)
+    }
+}
+
+pub mod asynch;
+pub mod concat;
+pub mod mock;
+pub mod storage;
@@ -0,0 +1,180 @@
+//! An in-memory emulated [`Flash`] for testing
+//!
+//! [`MockFlash`] enforces a [`DeviceProfile`]'s erase polarity, minimum write size/alignment,
+//! write-after-write policy, and (optionally) a per-erase-block write-count cap from its
+//! `program`, so the device-specific quirks noted in the crate root docs can be tested without
+//! real hardware.
+
+use crate::{Flash, FlashOp, ProgramError};
+use core::pin::Pin;
+
+/// Which transitions a `program` is allowed to make to bytes that are not still in their erased
+/// state (checked a byte at a time)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteAfterWrite {
+    /// A write may only ever push bits toward 0
+    BitsToZero,
+    /// A write may only ever push bits toward 1
+    BitsToOne,
+    /// A write may only set every targeted bit to 0
+    AllToZero,
+    /// A write may only set every targeted bit to 1
+    AllToOne,
+}
+
+/// The subset of a real device's flash controller behavior [`MockFlash`] needs to emulate it
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceProfile {
+    /// Does this device erase to 0 or 1?
+    pub erases_to_zero: bool,
+    /// Minimum write size (and alignment), in bytes
+    pub write_size: usize,
+    /// Size of one erase block, in bytes
+    pub eb_bytes: usize,
+    /// Permitted write-after-write transition
+    pub write_after_write: WriteAfterWrite,
+    /// Maximum number of `program` calls touching the same erase block before it must be erased
+    /// again, if the device enforces one (e.g. the NRF52's 181-writes-between-erases limit)
+    pub max_writes_per_eb: Option<u32>,
+}
+
+/// An in-memory [`Flash`] backed by a `Vec<u8>`, enforcing the constraints of a [`DeviceProfile`]
+pub struct MockFlash {
+    profile: DeviceProfile,
+    data: Vec<u8>,
+    /// Number of `program` calls since the last erase, per erase block
+    write_counts: Vec<u32>,
+    /// Test-only hook run in place of the normal "fill with the erased byte" behavior, letting a
+    /// test simulate power loss mid-erase by corrupting the block instead
+    erase_hook: Option<Box<dyn FnMut(&mut [u8])>>,
+}
+
+impl MockFlash {
+    pub fn new(profile: DeviceProfile, total_bytes: usize) -> Self {
+        assert_eq!(
+            total_bytes % profile.eb_bytes,
+            0,
+            "total_bytes must be a whole number of erase blocks"
+        );
+
+        let erased_byte = if profile.erases_to_zero { 0x00 } else { 0xff };
+        let eb_count = total_bytes / profile.eb_bytes;
+        Self {
+            data: vec![erased_byte; total_bytes],
+            write_counts: vec![0; eb_count],
+            profile,
+            erase_hook: None,
+        }
+    }
+
+    /// Raw contents of the emulated device, for test assertions
+    pub fn contents(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Install a hook to run on every `erase_sector` instead of filling the block with the erased
+    /// byte -- lets a test simulate power loss mid-erase by corrupting the block
+    pub fn set_erase_hook(&mut self, hook: impl FnMut(&mut [u8]) + 'static) {
+        self.erase_hook = Some(Box::new(hook));
+    }
+
+    fn erased_byte(&self) -> u8 {
+        if self.profile.erases_to_zero {
+            0x00
+        } else {
+            0xff
+        }
+    }
+
+    fn eb_index(&self, sector: usize) -> usize {
+        sector / self.profile.eb_bytes
+    }
+}
+
+impl Flash for MockFlash {
+    fn erases_to_zero(&self) -> bool {
+        self.profile.erases_to_zero
+    }
+
+    fn write_size(&self) -> usize {
+        self.profile.write_size
+    }
+
+    fn supports_multiwrite(&self) -> bool {
+        self.profile.max_writes_per_eb.map_or(true, |n| n > 1)
+    }
+
+    fn read(&self, sector: usize, addr: usize, buf: &mut [u8]) {
+        let start = sector + addr;
+        buf.copy_from_slice(&self.data[start..start + buf.len()]);
+    }
+
+    fn run_op(&mut self, _op: Pin<FlashOp<'_>>) {
+        // XXX: MockFlash is driven directly through `erase_sector`/`program`; nothing submits
+        // `FlashOp`s to it yet.
+        unimplemented!("MockFlash does not implement op queuing yet")
+    }
+
+    fn erase_sector(&mut self, sector: usize) -> Result<(), ()> {
+        let eb = self.eb_index(sector);
+        let erased_byte = self.erased_byte();
+        let block = &mut self.data[sector..sector + self.profile.eb_bytes];
+
+        match &mut self.erase_hook {
+            Some(hook) => hook(block),
+            None => block.fill(erased_byte),
+        }
+
+        self.write_counts[eb] = 0;
+        Ok(())
+    }
+
+    fn program(&mut self, sector: usize, addr: usize, data: &[u8]) -> Result<(), ProgramError> {
+        if self.profile.write_size != 0
+            && (addr % self.profile.write_size != 0 || data.len() % self.profile.write_size != 0)
+        {
+            return Err(ProgramError::WriteUnaligned);
+        }
+
+        let eb = self.eb_index(sector);
+        if let Some(max) = self.profile.max_writes_per_eb {
+            if self.write_counts[eb] >= max {
+                return Err(ProgramError::TooManyWrites);
+            }
+        }
+
+        let start = sector + addr;
+        let erased_byte = self.erased_byte();
+        let current = &self.data[start..start + data.len()];
+
+        for (&cur, &new) in current.iter().zip(data) {
+            // A bit may never move back toward the erased value via `program` -- only an erase
+            // does that.
+            let moved_to_erased = if self.profile.erases_to_zero {
+                cur & !new // bit was 1 (programmed), now asked for 0 (erased)
+            } else {
+                !cur & new // bit was 0 (programmed), now asked for 1 (erased)
+            };
+            if moved_to_erased != 0 {
+                return Err(ProgramError::BitUnsetAttempt);
+            }
+
+            // Still-erased bytes can take any value; the policy only governs overwrites.
+            let allowed = cur == erased_byte
+                || match self.profile.write_after_write {
+                    WriteAfterWrite::BitsToZero => !cur & new == 0, // no bit may go 0 -> 1
+                    WriteAfterWrite::BitsToOne => cur & !new == 0,  // no bit may go 1 -> 0
+                    WriteAfterWrite::AllToZero => new == 0 || cur == new,
+                    WriteAfterWrite::AllToOne => new == 0xff || cur == new,
+                };
+            if !allowed {
+                return Err(ProgramError::WriteAfterWrite);
+            }
+        }
+
+        self.data[start..start + data.len()].copy_from_slice(data);
+        self.write_counts[eb] += 1;
+
+        Ok(())
+    }
+}
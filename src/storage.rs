@@ -0,0 +1,235 @@
+//! Bridge a [`Flash`] + its [`FlashLayout`] onto the `embedded-storage` `nor_flash` traits
+//!
+//! `stm32f4xx-hal`, `stm32f1xx-hal`, `embassy-stm32` and most of the rest of the ecosystem drive
+//! flash through `embedded-storage`, not through [`Flash`] directly. [`FlashLayoutStorage`] lets
+//! an [`Mtxn`](crate::Mtxn) sit on top of any of those existing drivers instead of requiring a
+//! bespoke [`Flash`] impl per chip.
+//!
+//! `embedded_storage::nor_flash::NorFlash::ERASE_SIZE` is a single `const`, so this adapter only
+//! covers devices whose [`FlashLayout`] has a uniform erase block size across every region --
+//! `new` panics otherwise. Non-uniform layouts (e.g. STM32F7's 4x16K + 1x64K + 7x128K sectors)
+//! need `FlashLayout::into_regions` instead, one `FlashLayoutStorage` per region (each built over
+//! that region's `RegionHandle::layout`).
+
+use embedded_storage::nor_flash::{
+    ErrorType, MultiwriteNorFlash, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash,
+};
+use flash_layout::FlashLayout;
+
+use crate::{Flash, ProgramError};
+
+/// Adapts a [`Flash`] device plus its [`FlashLayout`] to the `embedded-storage` `nor_flash` traits
+///
+/// `WRITE_SIZE` and `ERASE_SIZE` are const generics rather than values read off `flash`/`layout`
+/// at runtime because `NorFlash::WRITE_SIZE`/`NorFlash::ERASE_SIZE` are themselves `const`s;
+/// `new` checks the const generics against the runtime device/layout and panics on mismatch.
+pub struct FlashLayoutStorage<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> {
+    flash: F,
+    layout: FlashLayout<'a>,
+}
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize>
+    FlashLayoutStorage<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+    pub fn new(flash: F, layout: FlashLayout<'a>) -> Self {
+        assert_eq!(
+            flash.write_size(),
+            WRITE_SIZE,
+            "WRITE_SIZE does not match the device's actual write size"
+        );
+        for region in layout.regions {
+            assert_eq!(
+                region.eb_bytes as usize, ERASE_SIZE,
+                "FlashLayoutStorage requires a uniform erase size; use FlashLayout::into_regions for non-uniform layouts"
+            );
+        }
+
+        Self { flash, layout }
+    }
+
+    /// Total addressable bytes, per [`FlashLayout::len`]
+    pub fn capacity(&self) -> u64 {
+        self.layout.len()
+    }
+
+    /// Promote this storage to a [`MultiwriteNorFlash`] implementor
+    ///
+    /// Only sound when the device erases to 1 and permits a second `program` to the same erase
+    /// block before the next erase -- panics otherwise.
+    pub fn into_multiwrite(self) -> Multiwrite<'a, F, WRITE_SIZE, ERASE_SIZE> {
+        assert!(
+            self.flash.supports_multiwrite(),
+            "device does not support repeated writes between erases"
+        );
+        assert!(
+            !self.flash.erases_to_zero(),
+            "MultiwriteNorFlash requires a device that erases to 1"
+        );
+        Multiwrite(self)
+    }
+}
+
+/// Error returned by [`FlashLayoutStorage`], mapped onto [`NorFlashErrorKind`]
+#[derive(Debug)]
+pub struct StorageError(NorFlashErrorKind);
+
+impl NorFlashError for StorageError {
+    fn kind(&self) -> NorFlashErrorKind {
+        self.0
+    }
+}
+
+impl From<ProgramError> for StorageError {
+    fn from(e: ProgramError) -> Self {
+        StorageError(match e {
+            ProgramError::WriteUnaligned => NorFlashErrorKind::NotAligned,
+            ProgramError::OutOfRange => NorFlashErrorKind::OutOfBounds,
+            ProgramError::BitUnsetAttempt
+            | ProgramError::TooManyWrites
+            | ProgramError::WriteAfterWrite => NorFlashErrorKind::Other,
+        })
+    }
+}
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> ErrorType
+    for FlashLayoutStorage<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+    type Error = StorageError;
+}
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> ReadNorFlash
+    for FlashLayoutStorage<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        let addr = self.layout.addr_start() + offset as u64;
+        if addr + bytes.len() as u64 > self.layout.addr_end() {
+            return Err(StorageError(NorFlashErrorKind::OutOfBounds));
+        }
+
+        let (eb, offs_in_eb) = self
+            .layout
+            .find_eb_by_addr(addr)
+            .ok_or(StorageError(NorFlashErrorKind::OutOfBounds))?;
+        if offs_in_eb as u64 + bytes.len() as u64 > eb.len() as u64 {
+            // Same as `write` below: `Flash::read` takes a sector + an offset within it, so a
+            // read spanning an erase block boundary would need splitting into per-block calls.
+            return Err(StorageError(NorFlashErrorKind::OutOfBounds));
+        }
+
+        self.flash
+            .read(eb.addr_start() as usize, offs_in_eb as usize, bytes);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.layout.len() as usize
+    }
+}
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> NorFlash
+    for FlashLayoutStorage<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+    const WRITE_SIZE: usize = WRITE_SIZE;
+    const ERASE_SIZE: usize = ERASE_SIZE;
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        // WRITE_SIZE == 0 is how a device with no minimum write size (see `MockFlash::program`)
+        // reaches this impl, since `new` requires WRITE_SIZE == flash.write_size(); skip the
+        // alignment check rather than dividing by zero.
+        if Self::WRITE_SIZE != 0
+            && (offset as usize % Self::WRITE_SIZE != 0 || bytes.len() % Self::WRITE_SIZE != 0)
+        {
+            return Err(StorageError(NorFlashErrorKind::NotAligned));
+        }
+
+        let addr = self.layout.addr_start() + offset as u64;
+        let (eb, offs_in_eb) = self
+            .layout
+            .find_eb_by_addr(addr)
+            .ok_or(StorageError(NorFlashErrorKind::OutOfBounds))?;
+        if offs_in_eb as u64 + bytes.len() as u64 > eb.len() as u64 {
+            // XXX: a write spanning an erase block boundary would need splitting into per-block
+            // `program` calls; reject it for now rather than silently doing the wrong thing.
+            return Err(StorageError(NorFlashErrorKind::OutOfBounds));
+        }
+
+        self.flash
+            .program(eb.addr_start() as usize, offs_in_eb as usize, bytes)
+            .map_err(StorageError::from)
+    }
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        let addr_start = self.layout.addr_start() + from as u64;
+        let addr_end = self.layout.addr_start() + to as u64;
+        // Guard the same way `write` does above, in case ERASE_SIZE is ever 0.
+        if ERASE_SIZE != 0
+            && (addr_start % ERASE_SIZE as u64 != 0 || addr_end % ERASE_SIZE as u64 != 0)
+        {
+            return Err(StorageError(NorFlashErrorKind::NotAligned));
+        }
+
+        let mut addr = addr_start;
+        while addr < addr_end {
+            let (eb, _) = self
+                .layout
+                .find_eb_by_addr(addr)
+                .ok_or(StorageError(NorFlashErrorKind::OutOfBounds))?;
+            self.flash
+                .erase_sector(eb.addr_start() as usize)
+                .map_err(|()| StorageError(NorFlashErrorKind::Other))?;
+            addr += ERASE_SIZE as u64;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`FlashLayoutStorage`] that has been verified, at construction time, to uphold the
+/// [`MultiwriteNorFlash`] contract
+///
+/// See [`FlashLayoutStorage::into_multiwrite`].
+pub struct Multiwrite<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize>(
+    FlashLayoutStorage<'a, F, WRITE_SIZE, ERASE_SIZE>,
+);
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> ErrorType
+    for Multiwrite<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+    type Error = StorageError;
+}
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> ReadNorFlash
+    for Multiwrite<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+    const READ_SIZE: usize = FlashLayoutStorage::<'a, F, WRITE_SIZE, ERASE_SIZE>::READ_SIZE;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.read(offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        ReadNorFlash::capacity(&self.0)
+    }
+}
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> NorFlash
+    for Multiwrite<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+    const WRITE_SIZE: usize = WRITE_SIZE;
+    const ERASE_SIZE: usize = ERASE_SIZE;
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(offset, bytes)
+    }
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        self.0.erase(from, to)
+    }
+}
+
+impl<'a, F: Flash, const WRITE_SIZE: usize, const ERASE_SIZE: usize> MultiwriteNorFlash
+    for Multiwrite<'a, F, WRITE_SIZE, ERASE_SIZE>
+{
+}
@@ -0,0 +1,63 @@
+use flash_layout::{FlashLayout, Region};
+use mtxn::concat::ConcatFlash;
+use mtxn::mock::{DeviceProfile, MockFlash, WriteAfterWrite};
+use mtxn::Flash;
+
+fn profile() -> DeviceProfile {
+    DeviceProfile {
+        erases_to_zero: false,
+        write_size: 4,
+        eb_bytes: 256,
+        write_after_write: WriteAfterWrite::BitsToZero,
+        max_writes_per_eb: None,
+    }
+}
+
+fn region(addr: u64) -> Region {
+    Region {
+        addr,
+        eb_bytes: 256,
+        eb_count: 1,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn routes_to_the_right_child_and_offsets_the_second_layout() {
+    let a_regions = [region(0)];
+    let b_regions = [region(0)];
+    let a_layout = FlashLayout::new(&a_regions);
+    let b_layout = FlashLayout::new(&b_regions);
+
+    let a = MockFlash::new(profile(), 256);
+    let b = MockFlash::new(profile(), 256);
+    let mut concat = ConcatFlash::new(a, a_layout, b, b_layout);
+
+    let layout = concat.layout();
+    assert_eq!(layout.addr_start(), 0);
+    assert_eq!(layout.addr_end(), 512);
+
+    concat.program(0, 0, &[1, 2, 3, 4]).unwrap();
+    concat.program(256, 0, &[5, 6, 7, 8]).unwrap();
+
+    let mut buf = [0u8; 4];
+    concat.read(0, 0, &mut buf);
+    assert_eq!(buf, [1, 2, 3, 4]);
+    concat.read(256, 0, &mut buf);
+    assert_eq!(buf, [5, 6, 7, 8]);
+}
+
+#[test]
+fn rejects_a_sector_outside_the_combined_layout() {
+    let a_regions = [region(0)];
+    let b_regions = [region(0)];
+    let a_layout = FlashLayout::new(&a_regions);
+    let b_layout = FlashLayout::new(&b_regions);
+
+    let a = MockFlash::new(profile(), 256);
+    let b = MockFlash::new(profile(), 256);
+    let mut concat = ConcatFlash::new(a, a_layout, b, b_layout);
+
+    // the combined layout only covers [0, 512); nothing owns 1000.
+    assert!(concat.erase_sector(1000).is_err());
+}
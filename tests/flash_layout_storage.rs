@@ -0,0 +1,141 @@
+use embedded_storage::nor_flash::{NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash};
+use flash_layout::{FlashLayout, Region};
+use mtxn::mock::{DeviceProfile, MockFlash, WriteAfterWrite};
+use mtxn::storage::FlashLayoutStorage;
+
+fn profile() -> DeviceProfile {
+    DeviceProfile {
+        erases_to_zero: false,
+        write_size: 4,
+        eb_bytes: 256,
+        write_after_write: WriteAfterWrite::BitsToZero,
+        max_writes_per_eb: None,
+    }
+}
+
+fn layout() -> [Region; 1] {
+    [Region {
+        addr: 0,
+        eb_bytes: 256,
+        eb_count: 2,
+        ..Default::default()
+    }]
+}
+
+#[test]
+fn write_then_read_back() {
+    let regions = layout();
+    let mut storage =
+        FlashLayoutStorage::<_, 4, 256>::new(MockFlash::new(profile(), 512), FlashLayout::new(&regions));
+
+    storage.write(0, &[1, 2, 3, 4]).unwrap();
+    storage.write(256, &[5, 6, 7, 8]).unwrap();
+
+    let mut buf = [0u8; 4];
+    storage.read(0, &mut buf).unwrap();
+    assert_eq!(buf, [1, 2, 3, 4]);
+    storage.read(256, &mut buf).unwrap();
+    assert_eq!(buf, [5, 6, 7, 8]);
+}
+
+#[test]
+fn write_rejects_misaligned_offset() {
+    let regions = layout();
+    let mut storage =
+        FlashLayoutStorage::<_, 4, 256>::new(MockFlash::new(profile(), 512), FlashLayout::new(&regions));
+
+    assert_eq!(
+        storage.write(1, &[0, 0, 0, 0]).unwrap_err().kind(),
+        NorFlashErrorKind::NotAligned
+    );
+}
+
+#[test]
+fn write_rejects_a_span_crossing_an_erase_block() {
+    let regions = layout();
+    let mut storage =
+        FlashLayoutStorage::<_, 4, 256>::new(MockFlash::new(profile(), 512), FlashLayout::new(&regions));
+
+    // offset 252 + 8 bytes would straddle the boundary at 256.
+    assert_eq!(
+        storage.write(252, &[0, 0, 0, 0, 0, 0, 0, 0]).unwrap_err().kind(),
+        NorFlashErrorKind::OutOfBounds
+    );
+}
+
+#[test]
+fn read_out_of_bounds_is_reported() {
+    let regions = layout();
+    let mut storage =
+        FlashLayoutStorage::<_, 4, 256>::new(MockFlash::new(profile(), 512), FlashLayout::new(&regions));
+
+    let mut buf = [0u8; 4];
+    assert_eq!(
+        storage.read(510, &mut buf).unwrap_err().kind(),
+        NorFlashErrorKind::OutOfBounds
+    );
+}
+
+#[test]
+fn write_then_erase_round_trips_through_find_eb_by_addr() {
+    let regions = layout();
+    let mut storage =
+        FlashLayoutStorage::<_, 4, 256>::new(MockFlash::new(profile(), 512), FlashLayout::new(&regions));
+
+    storage.write(0, &[0xaa, 0xaa, 0xaa, 0xaa]).unwrap();
+    storage.erase(0, 256).unwrap();
+
+    let mut buf = [0u8; 4];
+    storage.read(0, &mut buf).unwrap();
+    assert_eq!(buf, [0xff, 0xff, 0xff, 0xff]);
+}
+
+#[test]
+fn write_with_no_minimum_write_size_does_not_panic() {
+    // write_size: 0 means "no minimum" (see MockFlash::program); WRITE_SIZE must match it
+    // exactly (FlashLayoutStorage::new asserts this), so this is the only way to reach `write`
+    // with WRITE_SIZE == 0.
+    let profile = DeviceProfile {
+        write_size: 0,
+        ..profile()
+    };
+    let regions = layout();
+    let mut storage =
+        FlashLayoutStorage::<_, 0, 256>::new(MockFlash::new(profile, 512), FlashLayout::new(&regions));
+
+    storage.write(0, &[1, 2, 3, 4, 5]).unwrap();
+
+    let mut buf = [0u8; 5];
+    storage.read(0, &mut buf).unwrap();
+    assert_eq!(buf, [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn read_rejects_a_span_crossing_an_erase_block() {
+    let regions = layout();
+    let mut storage =
+        FlashLayoutStorage::<_, 4, 256>::new(MockFlash::new(profile(), 512), FlashLayout::new(&regions));
+
+    // offset 252 + 8 bytes would straddle the boundary at 256, same as the write() case above.
+    let mut buf = [0u8; 8];
+    assert_eq!(
+        storage.read(252, &mut buf).unwrap_err().kind(),
+        NorFlashErrorKind::OutOfBounds
+    );
+}
+
+#[test]
+fn into_multiwrite_allows_a_second_write_to_the_same_block() {
+    let regions = layout();
+    let storage =
+        FlashLayoutStorage::<_, 4, 256>::new(MockFlash::new(profile(), 512), FlashLayout::new(&regions));
+    let mut storage = storage.into_multiwrite();
+
+    storage.write(0, &[0x0f, 0xff, 0xff, 0xff]).unwrap();
+    // BitsToZero: only 1 -> 0 transitions are legal on a second write.
+    storage.write(0, &[0x00, 0xff, 0xff, 0xff]).unwrap();
+
+    let mut buf = [0u8; 4];
+    storage.read(0, &mut buf).unwrap();
+    assert_eq!(buf, [0x00, 0xff, 0xff, 0xff]);
+}
@@ -0,0 +1,101 @@
+use mtxn::mock::{DeviceProfile, MockFlash, WriteAfterWrite};
+use mtxn::{Flash, ProgramError};
+
+fn nrf52_profile() -> DeviceProfile {
+    // NRF52 NVM: erase to 1, 4 byte writes, 512 byte blocks, 181 writes before re-erase required.
+    DeviceProfile {
+        erases_to_zero: false,
+        write_size: 4,
+        eb_bytes: 512,
+        write_after_write: WriteAfterWrite::BitsToZero,
+        max_writes_per_eb: Some(181),
+    }
+}
+
+#[test]
+fn write_then_read_back() {
+    let mut flash = MockFlash::new(nrf52_profile(), 512);
+    flash.program(0, 0, &[0x0f, 0x0f, 0x0f, 0x0f]).unwrap();
+
+    let mut buf = [0u8; 4];
+    flash.read(0, 0, &mut buf);
+    assert_eq!(buf, [0x0f, 0x0f, 0x0f, 0x0f]);
+}
+
+#[test]
+fn unaligned_write_rejected() {
+    let mut flash = MockFlash::new(nrf52_profile(), 512);
+    assert_eq!(
+        flash.program(0, 1, &[0, 0, 0, 0]),
+        Err(ProgramError::WriteUnaligned)
+    );
+}
+
+#[test]
+fn bit_unset_requires_erase() {
+    let mut flash = MockFlash::new(nrf52_profile(), 512);
+    flash.program(0, 0, &[0x0f, 0xff, 0xff, 0xff]).unwrap();
+    // erases_to_zero is false here, so pushing a 0 bit back to 1 is an erase-only transition.
+    assert_eq!(
+        flash.program(0, 0, &[0xff, 0xff, 0xff, 0xff]),
+        Err(ProgramError::BitUnsetAttempt)
+    );
+}
+
+#[test]
+fn write_after_write_allows_bits_toward_zero() {
+    let mut flash = MockFlash::new(nrf52_profile(), 512);
+    flash.program(0, 0, &[0x0f, 0xff, 0xff, 0xff]).unwrap();
+    // BitsToZero: only 1 -> 0 transitions are legal on a second write.
+    assert_eq!(flash.program(0, 0, &[0x00, 0xff, 0xff, 0xff]), Ok(()));
+}
+
+#[test]
+fn write_count_cap_is_enforced() {
+    let mut flash = MockFlash::new(nrf52_profile(), 512);
+    for _ in 0..181 {
+        flash.program(0, 0, &[0x00, 0xff, 0xff, 0xff]).unwrap();
+    }
+    assert_eq!(
+        flash.program(0, 0, &[0x00, 0xff, 0xff, 0xff]),
+        Err(ProgramError::TooManyWrites)
+    );
+}
+
+#[test]
+fn erase_resets_write_count_and_contents() {
+    let mut flash = MockFlash::new(nrf52_profile(), 512);
+    flash.program(0, 0, &[0x00, 0x00, 0x00, 0x00]).unwrap();
+    flash.erase_sector(0).unwrap();
+
+    let mut buf = [0u8; 4];
+    flash.read(0, 0, &mut buf);
+    assert_eq!(buf, [0xff, 0xff, 0xff, 0xff]);
+
+    // write count was reset, so 181 fresh writes succeed again.
+    for _ in 0..181 {
+        flash.program(0, 0, &[0x00, 0xff, 0xff, 0xff]).unwrap();
+    }
+}
+
+#[test]
+fn erase_hook_can_simulate_power_loss() {
+    let mut flash = MockFlash::new(nrf52_profile(), 512);
+    flash.program(0, 0, &[0x00, 0x00, 0x00, 0x00]).unwrap();
+    flash.program(0, 256, &[0x00, 0x00, 0x00, 0x00]).unwrap();
+    flash.set_erase_hook(|block| {
+        // simulate power loss partway through erase: only the first half got reset.
+        let half = block.len() / 2;
+        block[..half].fill(0xff);
+    });
+    flash.erase_sector(0).unwrap();
+
+    // first half was reset by the (interrupted) erase...
+    let mut buf = [0u8; 4];
+    flash.read(0, 0, &mut buf);
+    assert_eq!(buf, [0xff, 0xff, 0xff, 0xff]);
+    // ...but the second half never got erased, so the old contents are still there.
+    let mut buf = [0u8; 4];
+    flash.read(0, 256, &mut buf);
+    assert_eq!(buf, [0x00, 0x00, 0x00, 0x00]);
+}
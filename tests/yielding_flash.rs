@@ -0,0 +1,83 @@
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use mtxn::asynch::{AsyncFlash, YieldingFlash};
+use mtxn::mock::{DeviceProfile, MockFlash, WriteAfterWrite};
+use mtxn::ProgramError;
+
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Drive a future to completion on this thread, returning its output and how many times it was
+/// woken -- `yield_now` wakes immediately, so this count also says how many times it yielded.
+fn block_on<F: Future>(fut: F) -> (F::Output, usize) {
+    let waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let w: Waker = waker.clone().into();
+    let mut cx = Context::from_waker(&w);
+    let mut fut = pin!(fut);
+
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return (out, waker.0.load(Ordering::Relaxed));
+        }
+    }
+}
+
+fn nrf52_profile() -> DeviceProfile {
+    DeviceProfile {
+        erases_to_zero: false,
+        write_size: 4,
+        eb_bytes: 512,
+        write_after_write: WriteAfterWrite::BitsToZero,
+        max_writes_per_eb: Some(181),
+    }
+}
+
+#[test]
+fn erase_sector_yields_once() {
+    let mut flash = YieldingFlash::new(MockFlash::new(nrf52_profile(), 512));
+    let (result, yields) = block_on(flash.erase_sector(0));
+    assert_eq!(result, Ok(()));
+    assert_eq!(yields, 1);
+}
+
+#[test]
+fn program_yields_once_per_chunk() {
+    let mut flash = YieldingFlash::new(MockFlash::new(nrf52_profile(), 512));
+    let data = [0x0fu8; 16]; // 4 write-size chunks
+    let (result, yields) = block_on(flash.program(0, 0, &data));
+    assert_eq!(result, Ok(()));
+    assert_eq!(yields, 4);
+}
+
+#[test]
+fn program_with_no_minimum_write_size_does_not_panic() {
+    // write_size: 0 means "no minimum" (see MockFlash::program); YieldingFlash must not try to
+    // chunk by a 0-sized chunk.
+    let profile = DeviceProfile {
+        write_size: 0,
+        ..nrf52_profile()
+    };
+    let mut flash = YieldingFlash::new(MockFlash::new(profile, 512));
+    let (result, yields) = block_on(flash.program(0, 0, &[1, 2, 3, 4, 5]));
+    assert_eq!(result, Ok(()));
+    assert_eq!(yields, 1);
+}
+
+#[test]
+fn program_propagates_underlying_error() {
+    let mut flash = YieldingFlash::new(MockFlash::new(nrf52_profile(), 512));
+    let (result, _yields) = block_on(flash.program(0, 1, &[0, 0, 0, 0]));
+    assert_eq!(result, Err(ProgramError::WriteUnaligned));
+}